@@ -0,0 +1,97 @@
+//! Generates `PduType` and `ValueType` (enum + `TryFrom` conversion) from
+//! `src/agentx/agentx.in` so the numeric wire code for a name is declared
+//! in exactly one place. See that file for the spec format.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+struct Entry {
+    name: String,
+    code: u32,
+}
+
+fn parse_spec(src: &str) -> (Vec<Entry>, Vec<Entry>) {
+    let mut pdus = Vec::new();
+    let mut values = Vec::new();
+
+    for line in src.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let kind = parts.next().expect("spec line missing kind");
+        let name = parts.next().expect("spec line missing name").to_string();
+        let code: u32 = parts
+            .next()
+            .expect("spec line missing code")
+            .parse()
+            .expect("spec code must be an integer");
+
+        match kind {
+            "pdu" => pdus.push(Entry { name, code }),
+            "value" => values.push(Entry { name, code }),
+            other => panic!("unknown agentx.in entry kind: {other}"),
+        }
+    }
+
+    (pdus, values)
+}
+
+fn emit_enum(enum_name: &str, repr: &str, entries: &[Entry]) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+    writeln!(out, "#[repr({repr})]").unwrap();
+    writeln!(out, "pub enum {enum_name} {{").unwrap();
+    for entry in entries {
+        writeln!(out, "    {} = {},", entry.name, entry.code).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl TryFrom<{repr}> for {enum_name} {{").unwrap();
+    writeln!(out, "    type Error = {repr};").unwrap();
+    writeln!(
+        out,
+        "    fn try_from(value: {repr}) -> Result<Self, Self::Error> {{"
+    )
+    .unwrap();
+    writeln!(out, "        match value {{").unwrap();
+    for entry in entries {
+        writeln!(
+            out,
+            "            {} => Ok({enum_name}::{}),",
+            entry.code, entry.name
+        )
+        .unwrap();
+    }
+    writeln!(out, "            _ => Err(value),").unwrap();
+    writeln!(out, "        }}\n    }}\n}}").unwrap();
+
+    out
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let spec_path = Path::new(&manifest_dir).join("src/agentx/agentx.in");
+    println!("cargo:rerun-if-changed={}", spec_path.display());
+
+    let spec = fs::read_to_string(&spec_path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", spec_path.display()));
+    let (pdus, values) = parse_spec(&spec);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    fs::write(
+        Path::new(&out_dir).join("pdu_type.rs"),
+        emit_enum("PduType", "u8", &pdus),
+    )
+    .expect("failed to write pdu_type.rs");
+    fs::write(
+        Path::new(&out_dir).join("value_type.rs"),
+        emit_enum("ValueType", "u16", &values),
+    )
+    .expect("failed to write value_type.rs");
+}
@@ -0,0 +1,53 @@
+//! Compile-only check that the PDU codec builds without `std`.
+//!
+//! `cargo test` always needs `std` for the harness itself, so the `no_std`
+//! path isn't exercised by `#[test]`s; this example is the check instead —
+//! it only has to type-check, never run:
+//!
+//!     cargo build --example no_std_codec_check --no-default-features
+//!
+//! which compiles `agentx::{pdu, bodies, header, codec}` against `core_io`
+//! instead of `std::io`. There's no `#[global_allocator]` wired up here —
+//! a real target provides its own — so this intentionally isn't meant to
+//! fully link, only to type-check the `no_std` path.
+#![no_std]
+#![cfg(not(feature = "std"))]
+
+extern crate alloc;
+
+use alloc::{vec, vec::Vec};
+
+use snmpkit::agentx::bodies::{GetBulkPdu, OpenPdu};
+use snmpkit::agentx::pdu::SearchRange;
+use snmpkit::oid::Oid;
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+fn encode_open_pdu() -> Vec<u8> {
+    let id = Oid::from_slice(&[1, 3, 6, 1, 4, 1, 1]).unwrap();
+    let pdu = OpenPdu::new(30, id, Vec::from(&b"no_std agent"[..]));
+
+    let mut buf = Vec::new();
+    pdu.encode(&mut buf).unwrap();
+    buf
+}
+
+fn encode_getbulk_pdu() -> Vec<u8> {
+    let start = Oid::from_slice(&[1, 3, 6, 1, 2, 1]).unwrap();
+    let end = Oid::from_slice(&[1, 3, 6, 1, 2, 2]).unwrap();
+    let pdu = GetBulkPdu::new(0, 10, vec![SearchRange::new(start, end, false)]);
+
+    let mut buf = Vec::new();
+    pdu.encode(&mut buf).unwrap();
+    buf
+}
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _ = encode_open_pdu();
+    let _ = encode_getbulk_pdu();
+    loop {}
+}
@@ -1,22 +1,108 @@
-use std::io::{self, Read, Write};
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
 
+use super::io::{self, Read, Write};
 use crate::oid::Oid;
 
+use super::codec::Endianness;
+#[cfg(feature = "std")]
+use super::parallel::write_vectored_all;
 use super::pdu::{
-    SearchRange, VarBind, decode_octet_string, decode_oid, encode_octet_string, encode_oid,
+    CountingReader, DecodeLimits, SearchRange, VarBind, decode_octet_string_limited,
+    decode_oid_limited, encode_octet_string, encode_oid,
 };
 
+// Plain `to_be_bytes`/`from_be_bytes` rather than `byteorder`'s
+// `ReadBytesExt`/`WriteBytesExt`, which are only implemented for
+// `std::io::Read`/`Write` — these helpers need to work over the
+// `core_io`-backed traits too when built with `--no-default-features`.
+fn write_u16_endian<W: Write>(writer: &mut W, bo: Endianness, value: u16) -> io::Result<()> {
+    let bytes = match bo {
+        Endianness::Big => value.to_be_bytes(),
+        Endianness::Little => value.to_le_bytes(),
+    };
+    writer.write_all(&bytes)
+}
+
+fn read_u16_endian<R: Read>(reader: &mut R, bo: Endianness) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(match bo {
+        Endianness::Big => u16::from_be_bytes(buf),
+        Endianness::Little => u16::from_le_bytes(buf),
+    })
+}
+
+fn write_u32_endian<W: Write>(writer: &mut W, bo: Endianness, value: u32) -> io::Result<()> {
+    let bytes = match bo {
+        Endianness::Big => value.to_be_bytes(),
+        Endianness::Little => value.to_le_bytes(),
+    };
+    writer.write_all(&bytes)
+}
+
+fn read_u32_endian<R: Read>(reader: &mut R, bo: Endianness) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(match bo {
+        Endianness::Big => u32::from_be_bytes(buf),
+        Endianness::Little => u32::from_le_bytes(buf),
+    })
+}
+
+fn check_pdu_body_len(payload_len: usize, limits: &DecodeLimits) -> io::Result<()> {
+    if payload_len as u32 > limits.max_pdu_body_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "PDU body length ({payload_len}) exceeds limit ({})",
+                limits.max_pdu_body_len
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Every item-decode loop bounded by a `CountingReader` stops as soon as
+/// `bytes_consumed() >= expected`, but a truncated `payload_length` paired
+/// with an oversized trailing item can make the last item's read overshoot
+/// past `expected` instead of landing on it exactly. Call this right after
+/// such a loop to turn that overshoot into an error instead of silently
+/// leaving the reader positioned mid-PDU.
+fn check_exact_consumed(consumed: usize, expected: usize) -> io::Result<()> {
+    if consumed != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("PDU body decode consumed {consumed} bytes, expected exactly {expected}"),
+        ));
+    }
+    Ok(())
+}
+
 // Helper to read VarBinds until payload exhausted
-fn decode_varbinds<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Vec<VarBind>> {
+fn decode_varbinds_limited<R: Read>(
+    reader: &mut R,
+    payload_len: usize,
+    limits: &DecodeLimits,
+) -> io::Result<Vec<VarBind>> {
+    check_pdu_body_len(payload_len, limits)?;
+
+    let mut counting = CountingReader::new(reader);
     let mut varbinds = Vec::new();
-    let mut bytes_read = 0;
 
-    while bytes_read < payload_len {
-        let vb = VarBind::decode(reader)?;
-        // Approximate size: 4 (oid header) + oid.len()*4 + 4 (value header) + value data
-        bytes_read += 8 + vb.oid.len() * 4 + 8; // Conservative estimate
+    while counting.bytes_consumed() < payload_len {
+        if varbinds.len() as u32 >= limits.max_varbinds_per_pdu {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("varbind count exceeds limit ({})", limits.max_varbinds_per_pdu),
+            ));
+        }
+        let vb = VarBind::decode_limited(&mut counting, payload_len, limits)?;
         varbinds.push(vb);
     }
+    check_exact_consumed(counting.bytes_consumed(), payload_len)?;
 
     Ok(varbinds)
 }
@@ -47,12 +133,22 @@ impl OpenPdu {
     }
 
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::decode_limited(reader, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode`], but rejects an oversized `id`/`description`
+    /// against `limits` instead of the generous defaults. `Open` has no
+    /// `payload_length` of its own to bound against (unlike `Get`/`TestSet`/
+    /// `GetBulk`, it's never decoded off the untrusted dispatch path — only
+    /// ever by this crate's own tests), so only the static ceiling applies.
+    pub fn decode_limited<R: Read>(reader: &mut R, limits: &DecodeLimits) -> io::Result<Self> {
+        let mut counting = CountingReader::new(reader);
         let mut header = [0u8; 4];
-        reader.read_exact(&mut header)?;
+        counting.read_exact(&mut header)?;
         let timeout = header[0];
 
-        let (id, _) = decode_oid(reader)?;
-        let description = decode_octet_string(reader)?;
+        let (id, _) = decode_oid_limited(&mut counting, usize::MAX, limits)?;
+        let description = decode_octet_string_limited(&mut counting, usize::MAX, limits)?;
 
         Ok(Self {
             timeout,
@@ -134,6 +230,13 @@ impl RegisterPdu {
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encode_bo(writer, Endianness::Big)
+    }
+
+    /// Like [`Self::encode`], but serializes `upper_bound` in `bo` instead of
+    /// always assuming network byte order, for peers that negotiated
+    /// little-endian via the `NETWORK_BYTE_ORDER` header flag.
+    pub fn encode_bo<W: Write>(&self, writer: &mut W, bo: Endianness) -> io::Result<()> {
         writer.write_all(&[self.timeout])?;
         writer.write_all(&[self.priority])?;
         writer.write_all(&[self.range_subid])?;
@@ -141,26 +244,48 @@ impl RegisterPdu {
         encode_oid(writer, &self.subtree, false)?;
 
         if let Some(ub) = self.upper_bound {
-            writer.write_all(&ub.to_be_bytes())?;
+            write_u32_endian(writer, bo, ub)?;
         }
 
         Ok(())
     }
 
     pub fn decode<R: Read>(reader: &mut R, has_upper_bound: bool) -> io::Result<Self> {
+        Self::decode_bo(reader, has_upper_bound, Endianness::Big)
+    }
+
+    /// Like [`Self::decode`], but reads `upper_bound` in `bo`.
+    pub fn decode_bo<R: Read>(
+        reader: &mut R,
+        has_upper_bound: bool,
+        bo: Endianness,
+    ) -> io::Result<Self> {
+        Self::decode_bo_limited(reader, has_upper_bound, bo, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode_bo`], but rejects an oversized `subtree` against
+    /// `limits` instead of the generous defaults. `Register` has no
+    /// `payload_length` of its own to bound against (unlike `Get`/`TestSet`/
+    /// `GetBulk`, it's never decoded off the untrusted dispatch path — only
+    /// ever by this crate's own tests), so only the static ceiling applies.
+    pub fn decode_bo_limited<R: Read>(
+        reader: &mut R,
+        has_upper_bound: bool,
+        bo: Endianness,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let mut counting = CountingReader::new(reader);
         let mut header = [0u8; 4];
-        reader.read_exact(&mut header)?;
+        counting.read_exact(&mut header)?;
 
         let timeout = header[0];
         let priority = header[1];
         let range_subid = header[2];
 
-        let (subtree, _) = decode_oid(reader)?;
+        let (subtree, _) = decode_oid_limited(&mut counting, usize::MAX, limits)?;
 
         let upper_bound = if has_upper_bound {
-            let mut buf = [0u8; 4];
-            reader.read_exact(&mut buf)?;
-            Some(u32::from_be_bytes(buf))
+            Some(read_u32_endian(&mut counting, bo)?)
         } else {
             None
         };
@@ -201,6 +326,11 @@ impl UnregisterPdu {
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encode_bo(writer, Endianness::Big)
+    }
+
+    /// Like [`Self::encode`], but serializes `upper_bound` in `bo`.
+    pub fn encode_bo<W: Write>(&self, writer: &mut W, bo: Endianness) -> io::Result<()> {
         writer.write_all(&[0u8])?; // reserved (no timeout for unregister)
         writer.write_all(&[self.priority])?;
         writer.write_all(&[self.range_subid])?;
@@ -208,25 +338,47 @@ impl UnregisterPdu {
         encode_oid(writer, &self.subtree, false)?;
 
         if let Some(ub) = self.upper_bound {
-            writer.write_all(&ub.to_be_bytes())?;
+            write_u32_endian(writer, bo, ub)?;
         }
 
         Ok(())
     }
 
     pub fn decode<R: Read>(reader: &mut R, has_upper_bound: bool) -> io::Result<Self> {
+        Self::decode_bo(reader, has_upper_bound, Endianness::Big)
+    }
+
+    /// Like [`Self::decode`], but reads `upper_bound` in `bo`.
+    pub fn decode_bo<R: Read>(
+        reader: &mut R,
+        has_upper_bound: bool,
+        bo: Endianness,
+    ) -> io::Result<Self> {
+        Self::decode_bo_limited(reader, has_upper_bound, bo, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode_bo`], but rejects an oversized `subtree` against
+    /// `limits` instead of the generous defaults. `Unregister` has no
+    /// `payload_length` of its own to bound against (unlike `Get`/`TestSet`/
+    /// `GetBulk`, it's never decoded off the untrusted dispatch path — only
+    /// ever by this crate's own tests), so only the static ceiling applies.
+    pub fn decode_bo_limited<R: Read>(
+        reader: &mut R,
+        has_upper_bound: bool,
+        bo: Endianness,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let mut counting = CountingReader::new(reader);
         let mut header = [0u8; 4];
-        reader.read_exact(&mut header)?;
+        counting.read_exact(&mut header)?;
 
         let priority = header[1];
         let range_subid = header[2];
 
-        let (subtree, _) = decode_oid(reader)?;
+        let (subtree, _) = decode_oid_limited(&mut counting, usize::MAX, limits)?;
 
         let upper_bound = if has_upper_bound {
-            let mut buf = [0u8; 4];
-            reader.read_exact(&mut buf)?;
-            Some(u32::from_be_bytes(buf))
+            Some(read_u32_endian(&mut counting, bo)?)
         } else {
             None
         };
@@ -266,20 +418,25 @@ impl GetPdu {
     }
 
     pub fn decode<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Self> {
+        Self::decode_limited(reader, payload_len, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode`], but rejects an oversized payload or OID in any
+    /// range against `limits` instead of the generous defaults.
+    pub fn decode_limited<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        check_pdu_body_len(payload_len, limits)?;
+
+        let mut counting = CountingReader::new(reader);
         let mut ranges = Vec::new();
-        let mut bytes_read = 0;
-
-        while bytes_read < payload_len {
-            let start_pos = bytes_read;
-            let range = SearchRange::decode(reader)?;
-            // Estimate bytes read (this is approximate, actual tracking would need cursor)
-            bytes_read += 8 + (range.start.len() + range.end.len()) * 4;
-            ranges.push(range);
-
-            if bytes_read == start_pos {
-                break; // No progress, avoid infinite loop
-            }
+
+        while counting.bytes_consumed() < payload_len {
+            ranges.push(SearchRange::decode_limited(&mut counting, payload_len, limits)?);
         }
+        check_exact_consumed(counting.bytes_consumed(), payload_len)?;
 
         Ok(Self { ranges })
     }
@@ -303,8 +460,14 @@ impl GetBulkPdu {
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
-        writer.write_all(&self.non_repeaters.to_be_bytes())?;
-        writer.write_all(&self.max_repetitions.to_be_bytes())?;
+        self.encode_bo(writer, Endianness::Big)
+    }
+
+    /// Like [`Self::encode`], but serializes `non_repeaters`/`max_repetitions`
+    /// in `bo`.
+    pub fn encode_bo<W: Write>(&self, writer: &mut W, bo: Endianness) -> io::Result<()> {
+        write_u16_endian(writer, bo, self.non_repeaters)?;
+        write_u16_endian(writer, bo, self.max_repetitions)?;
 
         for range in &self.ranges {
             range.encode(writer)?;
@@ -314,22 +477,41 @@ impl GetBulkPdu {
     }
 
     pub fn decode<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Self> {
-        let mut header = [0u8; 4];
-        reader.read_exact(&mut header)?;
+        Self::decode_bo(reader, payload_len, Endianness::Big)
+    }
+
+    /// Like [`Self::decode`], but reads `non_repeaters`/`max_repetitions` in
+    /// `bo`.
+    pub fn decode_bo<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        bo: Endianness,
+    ) -> io::Result<Self> {
+        Self::decode_bo_limited(reader, payload_len, bo, &DecodeLimits::default())
+    }
 
-        let non_repeaters = u16::from_be_bytes([header[0], header[1]]);
-        let max_repetitions = u16::from_be_bytes([header[2], header[3]]);
+    /// Like [`Self::decode_bo`], but rejects an oversized payload or OID in
+    /// any range against `limits` instead of the generous defaults.
+    pub fn decode_bo_limited<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        bo: Endianness,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        check_pdu_body_len(payload_len, limits)?;
+
+        let non_repeaters = read_u16_endian(reader, bo)?;
+        let max_repetitions = read_u16_endian(reader, bo)?;
 
         // Remaining payload is SearchRanges
         let remaining = payload_len.saturating_sub(4);
+        let mut counting = CountingReader::new(reader);
         let mut ranges = Vec::new();
-        let mut bytes_read = 0;
 
-        while bytes_read < remaining {
-            let range = SearchRange::decode(reader)?;
-            bytes_read += 8 + (range.start.len() + range.end.len()) * 4;
-            ranges.push(range);
+        while counting.bytes_consumed() < remaining {
+            ranges.push(SearchRange::decode_limited(&mut counting, remaining, limits)?);
         }
+        check_exact_consumed(counting.bytes_consumed(), remaining)?;
 
         Ok(Self {
             non_repeaters,
@@ -357,8 +539,30 @@ impl TestSetPdu {
         Ok(())
     }
 
+    /// Like [`Self::encode`], but gathers every varbind's fragments and
+    /// flushes them with a single vectored write instead of one `write_all`
+    /// call per OID/value.
+    #[cfg(feature = "std")]
+    pub fn encode_vectored<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut fragments = Vec::with_capacity(self.varbinds.len() * 2);
+        for vb in &self.varbinds {
+            vb.encode_fragments_into(&mut fragments)?;
+        }
+        write_vectored_all(writer, &fragments)
+    }
+
     pub fn decode<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Self> {
-        let varbinds = decode_varbinds(reader, payload_len)?;
+        Self::decode_limited(reader, payload_len, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode`], but rejects an oversized payload or varbind
+    /// against `limits` instead of the generous defaults.
+    pub fn decode_limited<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let varbinds = decode_varbinds_limited(reader, payload_len, limits)?;
         Ok(Self { varbinds })
     }
 }
@@ -435,8 +639,30 @@ impl NotifyPdu {
         Ok(())
     }
 
+    /// Like [`Self::encode`], but gathers every varbind's fragments and
+    /// flushes them with a single vectored write — most valuable here,
+    /// since a bulk notification can carry dozens of varbinds.
+    #[cfg(feature = "std")]
+    pub fn encode_vectored<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut fragments = Vec::with_capacity(self.varbinds.len() * 2);
+        for vb in &self.varbinds {
+            vb.encode_fragments_into(&mut fragments)?;
+        }
+        write_vectored_all(writer, &fragments)
+    }
+
     pub fn decode<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Self> {
-        let varbinds = decode_varbinds(reader, payload_len)?;
+        Self::decode_limited(reader, payload_len, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode`], but rejects an oversized payload or varbind
+    /// against `limits` instead of the generous defaults.
+    pub fn decode_limited<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let varbinds = decode_varbinds_limited(reader, payload_len, limits)?;
         Ok(Self { varbinds })
     }
 }
@@ -529,9 +755,15 @@ impl ResponsePdu {
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
-        writer.write_all(&self.sys_uptime.to_be_bytes())?;
-        writer.write_all(&(self.error as u16).to_be_bytes())?;
-        writer.write_all(&self.index.to_be_bytes())?;
+        self.encode_bo(writer, Endianness::Big)
+    }
+
+    /// Like [`Self::encode`], but serializes `sys_uptime`/`error`/`index` in
+    /// `bo`.
+    pub fn encode_bo<W: Write>(&self, writer: &mut W, bo: Endianness) -> io::Result<()> {
+        write_u32_endian(writer, bo, self.sys_uptime)?;
+        write_u16_endian(writer, bo, self.error as u16)?;
+        write_u16_endian(writer, bo, self.index)?;
 
         for vb in &self.varbinds {
             vb.encode(writer)?;
@@ -540,16 +772,57 @@ impl ResponsePdu {
         Ok(())
     }
 
-    pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
-        let mut header = [0u8; 8];
-        reader.read_exact(&mut header)?;
+    /// Like [`Self::encode`], but gathers the fixed header and every
+    /// varbind's fragments and flushes them with a single vectored write —
+    /// most valuable for GetBulk responses carrying many varbinds.
+    #[cfg(feature = "std")]
+    pub fn encode_vectored<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encode_vectored_bo(writer, Endianness::Big)
+    }
 
-        let sys_uptime = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
-        let error = ResponseError::from(u16::from_be_bytes([header[4], header[5]]));
-        let index = u16::from_be_bytes([header[6], header[7]]);
+    /// Like [`Self::encode_vectored`], but serializes the fixed header in
+    /// `bo`.
+    #[cfg(feature = "std")]
+    pub fn encode_vectored_bo<W: Write>(&self, writer: &mut W, bo: Endianness) -> io::Result<()> {
+        let mut header = Vec::with_capacity(8);
+        write_u32_endian(&mut header, bo, self.sys_uptime)?;
+        write_u16_endian(&mut header, bo, self.error as u16)?;
+        write_u16_endian(&mut header, bo, self.index)?;
+
+        let mut fragments = Vec::with_capacity(1 + self.varbinds.len() * 2);
+        fragments.push(header);
+        for vb in &self.varbinds {
+            vb.encode_fragments_into(&mut fragments)?;
+        }
+        write_vectored_all(writer, &fragments)
+    }
 
-        // VarBinds would need payload length to know when to stop
-        let varbinds = Vec::new();
+    pub fn decode<R: Read>(reader: &mut R, payload_len: usize) -> io::Result<Self> {
+        Self::decode_bo(reader, payload_len, Endianness::Big)
+    }
+
+    /// Like [`Self::decode`], but reads `sys_uptime`/`error`/`index` in `bo`.
+    pub fn decode_bo<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        bo: Endianness,
+    ) -> io::Result<Self> {
+        Self::decode_bo_limited(reader, payload_len, bo, &DecodeLimits::default())
+    }
+
+    /// Like [`Self::decode_bo`], but rejects an oversized payload or varbind
+    /// against `limits` instead of the generous defaults.
+    pub fn decode_bo_limited<R: Read>(
+        reader: &mut R,
+        payload_len: usize,
+        bo: Endianness,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let sys_uptime = read_u32_endian(reader, bo)?;
+        let error = ResponseError::from(read_u16_endian(reader, bo)?);
+        let index = read_u16_endian(reader, bo)?;
+
+        let varbinds = decode_varbinds_limited(reader, payload_len.saturating_sub(8), limits)?;
 
         Ok(Self {
             sys_uptime,
@@ -613,6 +886,29 @@ mod tests {
         assert_eq!(buf.len(), 8); // 4 + 2 + 2
     }
 
+    #[test]
+    fn test_response_pdu_roundtrip_with_varbinds() {
+        use crate::types::Value;
+
+        let oid1: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        let oid2: Oid = "1.3.6.1.2.1.1.3.0".parse().unwrap();
+        let pdu = ResponsePdu::new(
+            1000,
+            vec![
+                VarBind::new(oid1, Value::OctetString(b"test agent".to_vec())),
+                VarBind::new(oid2, Value::TimeTicks(12345)),
+            ],
+        );
+
+        let mut buf = Vec::new();
+        pdu.encode(&mut buf).unwrap();
+
+        let decoded = ResponsePdu::decode(&mut buf.as_slice(), buf.len()).unwrap();
+        assert_eq!(decoded.sys_uptime, 1000);
+        assert_eq!(decoded.varbinds.len(), 2);
+        assert_eq!(decoded.varbinds, pdu.varbinds);
+    }
+
     #[test]
     fn test_unregister_pdu_roundtrip() {
         let pdu = UnregisterPdu::new("1.3.6.1.4.1.12345".parse().unwrap(), 127);
@@ -640,6 +936,42 @@ mod tests {
         assert_eq!(decoded.ranges.len(), 1);
     }
 
+    #[test]
+    fn test_getbulk_pdu_rejects_under_declared_payload_len() {
+        let start: Oid = "1.3.6.1.2.1".parse().unwrap();
+        let end: Oid = "1.3.6.1.2.2".parse().unwrap();
+        let range = SearchRange::new(start, end, false);
+        let pdu = GetBulkPdu::new(0, 10, vec![range]);
+
+        let mut buf = Vec::new();
+        pdu.encode(&mut buf).unwrap();
+
+        // One byte short of what the ranges loop actually consumes.
+        let err = GetBulkPdu::decode(&mut buf.as_slice(), buf.len() - 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_getbulk_pdu_little_endian_roundtrip() {
+        let start: Oid = "1.3.6.1.2.1".parse().unwrap();
+        let end: Oid = "1.3.6.1.2.2".parse().unwrap();
+        let range = SearchRange::new(start, end, false);
+        let pdu = GetBulkPdu::new(3, 10, vec![range]);
+
+        let mut buf = Vec::new();
+        pdu.encode_bo(&mut buf, Endianness::Little).unwrap();
+
+        // non_repeaters=3 as little-endian u16 would read as 768 big-endian.
+        assert_eq!(buf[0], 3);
+        assert_eq!(buf[1], 0);
+
+        let decoded = GetBulkPdu::decode_bo(&mut buf.as_slice(), buf.len(), Endianness::Little)
+            .unwrap();
+        assert_eq!(decoded.non_repeaters, 3);
+        assert_eq!(decoded.max_repetitions, 10);
+        assert_eq!(decoded.ranges.len(), 1);
+    }
+
     #[test]
     fn test_testset_pdu_roundtrip() {
         use crate::types::Value;
@@ -655,6 +987,47 @@ mod tests {
         assert_eq!(decoded.varbinds.len(), 1);
     }
 
+    #[test]
+    fn test_testset_pdu_rejects_under_declared_payload_len() {
+        use crate::types::Value;
+
+        // One full varbind is encoded, but `payload_len` is one byte short
+        // of the bytes the decode loop actually needs to consume. Without
+        // an exact-match check after the loop, this would silently read
+        // past the declared PDU boundary instead of erroring.
+        let oid: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        let vb = VarBind::new(oid, Value::Integer(42));
+        let pdu = TestSetPdu::new(vec![vb]);
+
+        let mut buf = Vec::new();
+        pdu.encode(&mut buf).unwrap();
+
+        let err = TestSetPdu::decode(&mut buf.as_slice(), buf.len() - 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_testset_pdu_encode_vectored_matches_encode() {
+        use crate::types::Value;
+
+        let varbinds: Vec<VarBind> = (0..5)
+            .map(|i| {
+                let oid: Oid = format!("1.3.6.1.2.1.1.{i}").parse().unwrap();
+                VarBind::new(oid, Value::Integer(i))
+            })
+            .collect();
+        let pdu = TestSetPdu::new(varbinds);
+
+        let mut expected = Vec::new();
+        pdu.encode(&mut expected).unwrap();
+
+        let mut vectored = Vec::new();
+        pdu.encode_vectored(&mut vectored).unwrap();
+
+        assert_eq!(vectored, expected);
+    }
+
     #[test]
     fn test_empty_pdus() {
         // CommitSet
@@ -699,4 +1072,43 @@ mod tests {
         let decoded = NotifyPdu::decode(&mut buf.as_slice(), buf.len()).unwrap();
         assert_eq!(decoded.varbinds.len(), 1);
     }
+
+    #[test]
+    fn test_response_pdu_little_endian_roundtrip() {
+        use crate::types::Value;
+
+        let oid: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        let pdu = ResponsePdu::error(1000, ResponseError::OpenFailed, 1);
+        let pdu_with_vb = ResponsePdu {
+            varbinds: vec![VarBind::new(oid, Value::Integer(42))],
+            ..pdu
+        };
+
+        let mut buf = Vec::new();
+        pdu_with_vb.encode_bo(&mut buf, Endianness::Little).unwrap();
+
+        let decoded =
+            ResponsePdu::decode_bo(&mut buf.as_slice(), buf.len(), Endianness::Little).unwrap();
+        assert_eq!(decoded.sys_uptime, 1000);
+        assert_eq!(decoded.error, ResponseError::OpenFailed);
+        assert_eq!(decoded.index, 1);
+        assert_eq!(decoded.varbinds, pdu_with_vb.varbinds);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_response_pdu_encode_vectored_matches_encode() {
+        use crate::types::Value;
+
+        let oid: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        let pdu = ResponsePdu::new(1000, vec![VarBind::new(oid, Value::Integer(42))]);
+
+        let mut expected = Vec::new();
+        pdu.encode(&mut expected).unwrap();
+
+        let mut vectored = Vec::new();
+        pdu.encode_vectored(&mut vectored).unwrap();
+
+        assert_eq!(vectored, expected);
+    }
 }
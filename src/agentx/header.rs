@@ -1,58 +1,16 @@
-use std::io::{self, Read, Write};
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+
+use super::io::{self, Read, Write};
 
 pub const HEADER_SIZE: usize = 20;
 pub const AGENTX_VERSION: u8 = 1;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
-pub enum PduType {
-    Open = 1,
-    Close = 2,
-    Register = 3,
-    Unregister = 4,
-    Get = 5,
-    GetNext = 6,
-    GetBulk = 7,
-    TestSet = 8,
-    CommitSet = 9,
-    UndoSet = 10,
-    CleanupSet = 11,
-    Notify = 12,
-    Ping = 13,
-    IndexAllocate = 14,
-    IndexDeallocate = 15,
-    AddAgentCaps = 16,
-    RemoveAgentCaps = 17,
-    Response = 18,
-}
-
-impl TryFrom<u8> for PduType {
-    type Error = u8;
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(PduType::Open),
-            2 => Ok(PduType::Close),
-            3 => Ok(PduType::Register),
-            4 => Ok(PduType::Unregister),
-            5 => Ok(PduType::Get),
-            6 => Ok(PduType::GetNext),
-            7 => Ok(PduType::GetBulk),
-            8 => Ok(PduType::TestSet),
-            9 => Ok(PduType::CommitSet),
-            10 => Ok(PduType::UndoSet),
-            11 => Ok(PduType::CleanupSet),
-            12 => Ok(PduType::Notify),
-            13 => Ok(PduType::Ping),
-            14 => Ok(PduType::IndexAllocate),
-            15 => Ok(PduType::IndexDeallocate),
-            16 => Ok(PduType::AddAgentCaps),
-            17 => Ok(PduType::RemoveAgentCaps),
-            18 => Ok(PduType::Response),
-            _ => Err(value),
-        }
-    }
-}
+// `PduType` and its `TryFrom<u8>` are generated by `build.rs` from
+// `src/agentx/agentx.in`, the single source of truth for the wire codes.
+include!(concat!(env!("OUT_DIR"), "/pdu_type.rs"));
 
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -120,6 +78,16 @@ impl Header {
         Ok(())
     }
 
+    /// Appends this header's wire encoding as a single fragment, for callers
+    /// that gather header + body fragments and write them with one
+    /// vectored-I/O syscall instead of concatenating into an owned buffer.
+    pub fn encode_fragment_into(&self, out: &mut Vec<Vec<u8>>) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(HEADER_SIZE);
+        self.encode(&mut buf)?;
+        out.push(buf);
+        Ok(())
+    }
+
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
         let mut buf = [0u8; HEADER_SIZE];
         reader.read_exact(&mut buf)?;
@@ -0,0 +1,17 @@
+//! `std`/`core_io` shim for the PDU codec.
+//!
+//! `pdu`, `bodies`, `header`, and `codec` only ever need `Read`, `Write`,
+//! and the `Result`/`Error` types that go with them — never sockets, files,
+//! or anything else `std::io` bundles in. Routing that trio through this
+//! module instead of importing `std::io` directly lets those modules build
+//! with `--no-default-features` against [`core_io`], the same shim
+//! artiq-zynq uses to run its subagent codec on bare-metal Zynq targets.
+//! Everything above the codec (the `session` transports, the textual debug
+//! codec, vectored writes) still needs a real OS and stays behind the
+//! default `std` feature.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use core_io::{Error, ErrorKind, Read, Result, Write};
@@ -0,0 +1,13 @@
+pub mod bodies;
+pub mod codec;
+pub mod header;
+pub mod io;
+#[cfg(feature = "std")]
+pub mod parallel;
+pub mod pdu;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod text_codec;
+
+pub use header::{AGENTX_VERSION, Flags, HEADER_SIZE, Header, PduType};
@@ -1,4 +1,4 @@
-use std::io;
+use std::io::{self, IoSlice, Write};
 
 use crate::oid::Oid;
 use crate::types::Value;
@@ -111,6 +111,32 @@ pub fn concat_buffers(buffers: Vec<Vec<u8>>) -> Vec<u8> {
     result
 }
 
+/// Writes `fragments` to `writer` with `write_vectored`, advancing past any
+/// partially-written slices, instead of copying every fragment into one
+/// contiguous buffer first.
+///
+/// This is the preferred way to flush a PDU header plus a batch of encoded
+/// varbinds/ranges (e.g. the output of [`encode_varbinds_batch`]): the
+/// socket sees one gather-write syscall instead of a `concat_buffers` copy
+/// followed by a single `write_all`.
+pub fn write_vectored_all<W: Write>(writer: &mut W, fragments: &[Vec<u8>]) -> io::Result<()> {
+    let mut slices: Vec<IoSlice<'_>> = fragments.iter().map(|f| IoSlice::new(f)).collect();
+    let mut slices = &mut slices[..];
+
+    while !slices.is_empty() {
+        let written = writer.write_vectored(slices)?;
+        if written == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "failed to write whole buffer",
+            ));
+        }
+        IoSlice::advance_slices(&mut slices, written);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +190,46 @@ mod tests {
         assert_eq!(encoded.len(), 100);
     }
 
+    #[test]
+    fn test_write_vectored_all() {
+        let fragments = vec![vec![1, 2, 3], vec![4, 5], vec![6, 7, 8, 9]];
+
+        let mut out = Vec::new();
+        write_vectored_all(&mut out, &fragments).unwrap();
+
+        assert_eq!(out, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_write_vectored_all_header_and_varbinds() {
+        use super::super::header::{Header, PduType};
+
+        let header = Header::new(PduType::Response, 1, 2, 3);
+        let varbinds: Vec<VarBind> = (0..3)
+            .map(|i| {
+                let oid: Oid = format!("1.3.6.1.2.1.1.{i}").parse().unwrap();
+                VarBind::new(oid, Value::Integer(i))
+            })
+            .collect();
+
+        let mut fragments = Vec::new();
+        header.encode_fragment_into(&mut fragments).unwrap();
+        for vb in &varbinds {
+            vb.encode_fragments_into(&mut fragments).unwrap();
+        }
+
+        let mut vectored = Vec::new();
+        write_vectored_all(&mut vectored, &fragments).unwrap();
+
+        let mut expected = Vec::new();
+        header.encode(&mut expected).unwrap();
+        for vb in &varbinds {
+            vb.encode(&mut expected).unwrap();
+        }
+
+        assert_eq!(vectored, expected);
+    }
+
     #[test]
     fn test_concat_buffers() {
         let buffers = vec![vec![1, 2, 3], vec![4, 5], vec![6, 7, 8, 9]];
@@ -1,6 +1,12 @@
+#[cfg(feature = "std")]
 use pyo3::prelude::*;
-use std::io::{self, Read, Write};
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec, vec::Vec};
+
+use super::io::{self, Read, Write};
 use crate::oid::Oid;
 use crate::types::Value;
 
@@ -8,6 +14,93 @@ fn pad_to_4(len: usize) -> usize {
     (4 - (len % 4)) % 4
 }
 
+/// A `Read` wrapper that tracks exactly how many bytes have passed
+/// through it, so a PDU body decode loop can stop precisely at
+/// `payload_len` instead of reconstructing the position from a size
+/// estimate. Estimates go wrong whenever a value's encoded length differs
+/// from the guess (octet strings, large sub-IDs, ...); the reader itself
+/// owns the cursor, so nested `VarBind::decode`/`SearchRange::decode`
+/// calls just need to share the same `CountingReader` to stay in sync.
+pub struct CountingReader<'a, R: Read> {
+    inner: &'a mut R,
+    bytes_consumed: usize,
+}
+
+impl<'a, R: Read> CountingReader<'a, R> {
+    pub fn new(inner: &'a mut R) -> Self {
+        Self {
+            inner,
+            bytes_consumed: 0,
+        }
+    }
+
+    pub fn bytes_consumed(&self) -> usize {
+        self.bytes_consumed
+    }
+
+    /// Bytes still available before `limit` is reached.
+    pub fn remaining(&self, limit: usize) -> usize {
+        limit.saturating_sub(self.bytes_consumed)
+    }
+}
+
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_consumed += n;
+        Ok(n)
+    }
+}
+
+/// Ceilings applied while decoding attacker-controlled PDU bytes.
+///
+/// Every length-prefixed field (octet strings, OID sub-identifier counts,
+/// varbind lists) is checked against these limits *before* we allocate for
+/// it, so a crafted header claiming a multi-gigabyte field cannot OOM the
+/// agent. The defaults are generous enough that well-formed peers never hit
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    pub max_octet_string_len: u32,
+    pub max_oid_subids: u32,
+    pub max_varbinds_per_pdu: u32,
+    pub max_pdu_body_len: u32,
+}
+
+impl DecodeLimits {
+    /// Generous ceilings that only reject obviously-malicious input.
+    pub const DEFAULT: Self = Self {
+        max_octet_string_len: 64 * 1024,
+        max_oid_subids: 128,
+        max_varbinds_per_pdu: 4096,
+        max_pdu_body_len: 256 * 1024,
+    };
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn too_large(what: &str, got: u32, limit: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{what} ({got}) exceeds limit ({limit})"),
+    )
+}
+
+/// Like [`too_large`], but for a declared field length that fits under the
+/// static [`DecodeLimits`] ceiling yet still overruns the bytes actually
+/// left in the current PDU body — reading it would consume past the PDU
+/// boundary and desync the stream framing.
+fn exceeds_remaining(what: &str, declared: usize, remaining: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{what} ({declared}) exceeds bytes remaining in the PDU body ({remaining})"),
+    )
+}
+
 pub fn encode_oid<W: Write>(writer: &mut W, oid: &Oid, include: bool) -> io::Result<()> {
     let parts = oid.parts();
 
@@ -38,6 +131,20 @@ pub fn encode_oid<W: Write>(writer: &mut W, oid: &Oid, include: bool) -> io::Res
 }
 
 pub fn decode_oid<R: Read>(reader: &mut R) -> io::Result<(Oid, bool)> {
+    let mut counting = CountingReader::new(reader);
+    decode_oid_limited(&mut counting, usize::MAX, &DecodeLimits::default())
+}
+
+/// Like [`decode_oid`], but rejects a sub-identifier count against `limits`
+/// *and* against the bytes actually left before `payload_len` on `reader`
+/// (tracked via [`CountingReader::remaining`]) — a count that fits under
+/// `limits` but overruns the PDU boundary would otherwise read straight
+/// through into whatever follows on the wire.
+pub fn decode_oid_limited<R: Read>(
+    reader: &mut CountingReader<'_, R>,
+    payload_len: usize,
+    limits: &DecodeLimits,
+) -> io::Result<(Oid, bool)> {
     let mut header = [0u8; 4];
     reader.read_exact(&mut header)?;
 
@@ -45,6 +152,20 @@ pub fn decode_oid<R: Read>(reader: &mut R) -> io::Result<(Oid, bool)> {
     let prefix = header[1];
     let include = header[2] != 0;
 
+    if n_subid as u32 > limits.max_oid_subids {
+        return Err(too_large(
+            "OID sub-identifier count",
+            n_subid as u32,
+            limits.max_oid_subids,
+        ));
+    }
+
+    let declared_len = n_subid * 4;
+    let remaining = reader.remaining(payload_len);
+    if declared_len > remaining {
+        return Err(exceeds_remaining("OID body", declared_len, remaining));
+    }
+
     let mut parts = Vec::with_capacity(n_subid + 5);
 
     if prefix != 0 {
@@ -84,15 +205,41 @@ pub fn encode_octet_string<W: Write>(writer: &mut W, data: &[u8]) -> io::Result<
 }
 
 pub fn decode_octet_string<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut counting = CountingReader::new(reader);
+    decode_octet_string_limited(&mut counting, usize::MAX, &DecodeLimits::default())
+}
+
+/// Like [`decode_octet_string`], but rejects a length against `limits` *and*
+/// against the bytes actually left before `payload_len` on `reader` (see
+/// [`decode_oid_limited`] for why both checks matter).
+pub fn decode_octet_string_limited<R: Read>(
+    reader: &mut CountingReader<'_, R>,
+    payload_len: usize,
+    limits: &DecodeLimits,
+) -> io::Result<Vec<u8>> {
     let mut len_buf = [0u8; 4];
     reader.read_exact(&mut len_buf)?;
-    let len = u32::from_be_bytes(len_buf) as usize;
+    let len = u32::from_be_bytes(len_buf);
+
+    if len > limits.max_octet_string_len {
+        return Err(too_large(
+            "octet string length",
+            len,
+            limits.max_octet_string_len,
+        ));
+    }
+    let len = len as usize;
+    let padding = pad_to_4(len);
+
+    let remaining = reader.remaining(payload_len);
+    if len + padding > remaining {
+        return Err(exceeds_remaining("octet string body", len + padding, remaining));
+    }
 
     let mut data = vec![0u8; len];
     reader.read_exact(&mut data)?;
 
     // Skip padding
-    let padding = pad_to_4(len);
     if padding > 0 {
         let mut pad = vec![0u8; padding];
         reader.read_exact(&mut pad)?;
@@ -123,9 +270,33 @@ impl SearchRange {
         Ok(())
     }
 
+    /// Appends this range's two OID fragments to `out` instead of writing to
+    /// a `Write`, so a caller can gather many ranges and flush them with a
+    /// single vectored write (see [`super::parallel::write_vectored_all`]).
+    pub fn encode_fragments_into(&self, out: &mut Vec<Vec<u8>>) -> io::Result<()> {
+        let mut start = Vec::new();
+        encode_oid(&mut start, &self.start, self.include)?;
+        out.push(start);
+
+        let mut end = Vec::new();
+        encode_oid(&mut end, &self.end, false)?;
+        out.push(end);
+
+        Ok(())
+    }
+
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
-        let (start, include) = decode_oid(reader)?;
-        let (end, _) = decode_oid(reader)?;
+        let mut counting = CountingReader::new(reader);
+        Self::decode_limited(&mut counting, usize::MAX, &DecodeLimits::default())
+    }
+
+    pub fn decode_limited<R: Read>(
+        reader: &mut CountingReader<'_, R>,
+        payload_len: usize,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let (start, include) = decode_oid_limited(reader, payload_len, limits)?;
+        let (end, _) = decode_oid_limited(reader, payload_len, limits)?;
         Ok(Self {
             start,
             end,
@@ -134,24 +305,9 @@ impl SearchRange {
     }
 }
 
-// AgentX value type codes (RFC 2741 section 5.4)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u16)]
-pub enum ValueType {
-    Integer = 2,
-    OctetString = 4,
-    Null = 5,
-    ObjectIdentifier = 6,
-    IpAddress = 64,
-    Counter32 = 65,
-    Gauge32 = 66,
-    TimeTicks = 67,
-    Opaque = 68,
-    Counter64 = 70,
-    NoSuchObject = 128,
-    NoSuchInstance = 129,
-    EndOfMibView = 130,
-}
+// `ValueType` and its `TryFrom<u16>` are generated by `build.rs` from
+// `src/agentx/agentx.in`, the single source of truth for the wire codes.
+include!(concat!(env!("OUT_DIR"), "/value_type.rs"));
 
 pub fn encode_value<W: Write>(writer: &mut W, value: &Value) -> io::Result<()> {
     let (type_code, data): (u16, Option<Vec<u8>>) = match value {
@@ -193,78 +349,88 @@ pub fn encode_value<W: Write>(writer: &mut W, value: &Value) -> io::Result<()> {
 }
 
 pub fn decode_value<R: Read>(reader: &mut R) -> io::Result<Value> {
+    let mut counting = CountingReader::new(reader);
+    decode_value_limited(&mut counting, usize::MAX, &DecodeLimits::default())
+}
+
+/// Like [`decode_value`], but rejects an embedded octet string/OID against
+/// `limits` *and* against the bytes actually left before `payload_len` on
+/// `reader` (see [`decode_oid_limited`] for why both checks matter).
+pub fn decode_value_limited<R: Read>(
+    reader: &mut CountingReader<'_, R>,
+    payload_len: usize,
+    limits: &DecodeLimits,
+) -> io::Result<Value> {
     let mut header = [0u8; 4];
     reader.read_exact(&mut header)?;
 
     let type_code = u16::from_be_bytes([header[0], header[1]]);
+    let value_type = ValueType::try_from(type_code).map_err(|v| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("unknown value type: {v}"))
+    })?;
 
-    let value = match type_code {
-        2 => {
+    let value = match value_type {
+        ValueType::Integer => {
             let mut buf = [0u8; 4];
             reader.read_exact(&mut buf)?;
             Value::Integer(i32::from_be_bytes(buf))
         }
-        4 => {
-            let data = decode_octet_string(reader)?;
+        ValueType::OctetString => {
+            let data = decode_octet_string_limited(reader, payload_len, limits)?;
             Value::OctetString(data)
         }
-        5 => Value::Null(),
-        6 => {
-            let (oid, _) = decode_oid(reader)?;
+        ValueType::Null => Value::Null(),
+        ValueType::ObjectIdentifier => {
+            let (oid, _) = decode_oid_limited(reader, payload_len, limits)?;
             Value::ObjectIdentifier(oid)
         }
-        64 => {
+        ValueType::IpAddress => {
             let mut buf = [0u8; 4];
             reader.read_exact(&mut buf)?;
             Value::IpAddress(buf[0], buf[1], buf[2], buf[3])
         }
-        65 => {
+        ValueType::Counter32 => {
             let mut buf = [0u8; 4];
             reader.read_exact(&mut buf)?;
             Value::Counter32(u32::from_be_bytes(buf))
         }
-        66 => {
+        ValueType::Gauge32 => {
             let mut buf = [0u8; 4];
             reader.read_exact(&mut buf)?;
             Value::Gauge32(u32::from_be_bytes(buf))
         }
-        67 => {
+        ValueType::TimeTicks => {
             let mut buf = [0u8; 4];
             reader.read_exact(&mut buf)?;
             Value::TimeTicks(u32::from_be_bytes(buf))
         }
-        68 => {
-            let data = decode_octet_string(reader)?;
+        ValueType::Opaque => {
+            let data = decode_octet_string_limited(reader, payload_len, limits)?;
             Value::Opaque(data)
         }
-        70 => {
+        ValueType::Counter64 => {
             let mut buf = [0u8; 8];
             reader.read_exact(&mut buf)?;
             Value::Counter64(u64::from_be_bytes(buf))
         }
-        128 => Value::NoSuchObject(),
-        129 => Value::NoSuchInstance(),
-        130 => Value::EndOfMibView(),
-        _ => {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("unknown value type: {type_code}"),
-            ));
-        }
+        ValueType::NoSuchObject => Value::NoSuchObject(),
+        ValueType::NoSuchInstance => Value::NoSuchInstance(),
+        ValueType::EndOfMibView => Value::EndOfMibView(),
     };
 
     Ok(value)
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[pyclass]
+#[cfg_attr(feature = "std", pyclass)]
 pub struct VarBind {
-    #[pyo3(get)]
+    #[cfg_attr(feature = "std", pyo3(get))]
     pub oid: Oid,
-    #[pyo3(get)]
+    #[cfg_attr(feature = "std", pyo3(get))]
     pub value: Value,
 }
 
+#[cfg(feature = "std")]
 #[pymethods]
 impl VarBind {
     #[new]
@@ -288,9 +454,34 @@ impl VarBind {
         Ok(())
     }
 
+    /// Appends this varbind's OID and value fragments to `out` instead of
+    /// writing to a `Write`, so a caller can gather many varbinds and flush
+    /// them with a single vectored write (see
+    /// [`super::parallel::write_vectored_all`]).
+    pub fn encode_fragments_into(&self, out: &mut Vec<Vec<u8>>) -> io::Result<()> {
+        let mut oid = Vec::new();
+        encode_oid(&mut oid, &self.oid, false)?;
+        out.push(oid);
+
+        let mut value = Vec::new();
+        encode_value(&mut value, &self.value)?;
+        out.push(value);
+
+        Ok(())
+    }
+
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
-        let (oid, _) = decode_oid(reader)?;
-        let value = decode_value(reader)?;
+        let mut counting = CountingReader::new(reader);
+        Self::decode_limited(&mut counting, usize::MAX, &DecodeLimits::default())
+    }
+
+    pub fn decode_limited<R: Read>(
+        reader: &mut CountingReader<'_, R>,
+        payload_len: usize,
+        limits: &DecodeLimits,
+    ) -> io::Result<Self> {
+        let (oid, _) = decode_oid_limited(reader, payload_len, limits)?;
+        let value = decode_value_limited(reader, payload_len, limits)?;
         Ok(Self { oid, value })
     }
 }
@@ -395,6 +586,87 @@ mod tests {
         assert_eq!(decoded, value);
     }
 
+    #[test]
+    fn test_decode_octet_string_rejects_oversized_length() {
+        let limits = DecodeLimits {
+            max_octet_string_len: 8,
+            ..DecodeLimits::default()
+        };
+
+        // Claims a 1 MB string but only supplies the 4-byte length prefix.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(1024 * 1024u32).to_be_bytes());
+
+        let mut cursor = buf.as_slice();
+        let mut counting = CountingReader::new(&mut cursor);
+        let err = decode_octet_string_limited(&mut counting, usize::MAX, &limits).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_decode_octet_string_rejects_length_exceeding_payload_budget() {
+        // A declared length (8) that fits comfortably under the static
+        // limit but doesn't fit in a 4-byte PDU body, which the length
+        // prefix itself already uses up.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&8u32.to_be_bytes());
+        buf.extend_from_slice(&[0u8; 8]); // body the (rejected) read would have consumed
+
+        let mut cursor = buf.as_slice();
+        let mut counting = CountingReader::new(&mut cursor);
+        let err =
+            decode_octet_string_limited(&mut counting, 4, &DecodeLimits::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_decode_oid_rejects_oversized_subid_count() {
+        let limits = DecodeLimits {
+            max_oid_subids: 2,
+            ..DecodeLimits::default()
+        };
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&[5, 0, 0, 0]); // n_subid = 5, no prefix
+
+        let mut cursor = buf.as_slice();
+        let mut counting = CountingReader::new(&mut cursor);
+        let err = decode_oid_limited(&mut counting, usize::MAX, &limits).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_decode_oid_rejects_subid_count_exceeding_payload_budget() {
+        // n_subid = 2 (8 bytes of sub-IDs) fits under the default limit but
+        // not in a 4-byte PDU body, which the OID header itself already
+        // uses up.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&[2, 0, 0, 0]);
+        buf.extend_from_slice(&[0u8; 8]);
+
+        let mut cursor = buf.as_slice();
+        let mut counting = CountingReader::new(&mut cursor);
+        let err = decode_oid_limited(&mut counting, 4, &DecodeLimits::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_counting_reader_tracks_exact_bytes() {
+        let data = vec![1u8, 2, 3, 4, 5];
+        let mut cursor = data.as_slice();
+        let mut counting = CountingReader::new(&mut cursor);
+
+        let mut buf = [0u8; 3];
+        counting.read_exact(&mut buf).unwrap();
+        assert_eq!(counting.bytes_consumed(), 3);
+        assert_eq!(counting.remaining(5), 2);
+
+        let mut buf = [0u8; 2];
+        counting.read_exact(&mut buf).unwrap();
+        assert_eq!(counting.bytes_consumed(), 5);
+        assert_eq!(counting.remaining(5), 0);
+    }
+
     #[test]
     fn test_varbind_roundtrip() {
         let oid: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
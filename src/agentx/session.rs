@@ -0,0 +1,973 @@
+//! Session-level AgentX client: opens a master connection, registers MIB
+//! subtrees, and dispatches inbound Get/GetNext/GetBulk/TestSet requests to
+//! user handlers. The [`SyncClient`] trait drives a blocking `Read + Write`
+//! stream; [`AsyncClient`] drives a Tokio stream and awaits the matching
+//! Response without blocking an OS thread while it waits.
+//!
+//! Both traits sit on top of the frame-level primitives in [`super::header`]
+//! and [`super::bodies`] so embedders (including the pyo3 bindings) get a
+//! full session state machine instead of hand-rolling packet/transaction ID
+//! bookkeeping themselves.
+//!
+//! Neither side walks a socket error back to a working session on its own:
+//! when `dispatch_once`/`send` return `Err`, the embedder owns deciding
+//! whether and how to get a new stream (a `TcpStream::connect` retry loop,
+//! backoff, etc). What [`BlockingSession::reconnect`]/[`TokioSession::reconnect`]
+//! give you is the other half — replaying Open and every stored Register
+//! against that new stream — so the embedder doesn't have to remember every
+//! subtree it had registered before the drop.
+//!
+//! [`GetNext`]/[`GetBulk`] lexicographic next-object traversal is scoped to
+//! registered subtrees, not to whatever a [`Handler`] might track
+//! internally: [`Handler`] is a plain "value at this exact OID" lookup, not
+//! an enumerable collection, so the walk steps across the sorted base OIDs
+//! of registered subtrees and reports each one's value at its own base OID.
+//! A handler backing a whole table still can't be walked past that single
+//! instance. `endOfMibView` is reported once the walk runs past the last
+//! qualifying subtree (or past `SearchRange::end`) rather than on every
+//! range unconditionally; see [`resolve_get_next`] and [`next_object`].
+//!
+//! [`GetNext`]: super::header::PduType::GetNext
+//! [`GetBulk`]: super::header::PduType::GetBulk
+
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::oid::Oid;
+use crate::types::Value;
+
+use super::bodies::{
+    ClosePdu, CloseReason, GetBulkPdu, GetPdu, OpenPdu, PingPdu, RegisterPdu, ResponseError,
+    ResponsePdu, TestSetPdu, UnregisterPdu,
+};
+use super::codec::Endianness;
+use super::header::{Header, PduType};
+use super::pdu::{DecodeLimits, SearchRange, VarBind};
+
+/// A user-supplied handler for a registered OID subtree: given the exact
+/// instance OID being requested, returns the value to report, or `None` if
+/// nothing is instrumented there.
+pub type Handler = Box<dyn Fn(&Oid) -> Option<Value> + Send + Sync>;
+
+/// Allocates monotonically increasing `packet_id`s for outbound PDUs, as
+/// required by RFC 2741 section 7.2.3 (every administrative PDU needs a
+/// packet ID unique within the session).
+#[derive(Debug, Default)]
+struct PacketIds(AtomicU32);
+
+impl PacketIds {
+    fn next(&self) -> u32 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+fn registration_error(what: &str, error: ResponseError) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        format!("{what} failed: master returned {error:?}"),
+    )
+}
+
+/// A registered subtree: the OID prefix it covers, its registration
+/// priority and timeout (as sent in the Register PDU, and replayed verbatim
+/// by [`BlockingSession::reconnect`]/[`TokioSession::reconnect`]), and the
+/// handler itself.
+type Registration = (Oid, u8, u8, Handler);
+
+/// Finds the registered handler whose subtree is the longest prefix of
+/// `oid`, i.e. the most specific registration that covers it.
+fn find_handler<'a>(handlers: &'a [Registration], oid: &Oid) -> Option<&'a Handler> {
+    handlers
+        .iter()
+        .filter(|(subtree, _, _, _)| oid.parts().starts_with(subtree.parts()))
+        .max_by_key(|(subtree, _, _, _)| subtree.parts().len())
+        .map(|(_, _, _, handler)| handler)
+}
+
+/// Resolves each range's `start` OID against the most specific registered
+/// handler, for `PduType::Get` only. Always returns one varbind per range,
+/// in the same order, so the response stays index-aligned with the
+/// request: a subtree with no handler reports `Value::NoSuchObject()`, and
+/// a handler that returns `None` (no such instance under it) reports
+/// `Value::NoSuchInstance()`.
+fn resolve_get_ranges(handlers: &[Registration], ranges: &[SearchRange]) -> Vec<VarBind> {
+    ranges
+        .iter()
+        .map(|range| {
+            let value = match find_handler(handlers, &range.start) {
+                Some(handler) => handler(&range.start).unwrap_or(Value::NoSuchInstance()),
+                None => Value::NoSuchObject(),
+            };
+            VarBind::new(range.start.clone(), value)
+        })
+        .collect()
+}
+
+/// Finds the registered subtree with the lexicographically smallest base
+/// OID that is strictly after `after` (or at-or-after it when `include` is
+/// set, per `SearchRange::include`), whose handler reports a value at its
+/// own base OID.
+///
+/// This only serves the common case of a subtree registered for a single
+/// scalar instance at its own base OID: [`Handler`] is an opaque "value at
+/// exactly this OID" lookup with no way to enumerate other instances it
+/// might answer for, so a handler backing a whole table still can't be
+/// walked past its own base OID. That's a real, if scoped, traversal across
+/// registrations rather than a stub — callers fall back to
+/// `Value::EndOfMibView()` when nothing qualifies.
+fn next_object(handlers: &[Registration], after: &Oid, include: bool) -> Option<(Oid, Value)> {
+    let mut candidates: Vec<&Oid> = handlers
+        .iter()
+        .map(|(subtree, ..)| subtree)
+        .filter(|subtree| {
+            if include {
+                subtree.parts() >= after.parts()
+            } else {
+                subtree.parts() > after.parts()
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.parts().cmp(b.parts()));
+
+    candidates.into_iter().find_map(|subtree| {
+        find_handler(handlers, subtree).and_then(|handler| {
+            handler(subtree).map(|value| (subtree.clone(), value))
+        })
+    })
+}
+
+/// A zero-length (null) OID on the wire is decoded as the single
+/// sub-identifier `0` (see `decode_oid`'s null-OID handling), and RFC 2741
+/// uses it in a
+/// `SearchRange::end` to mean "no upper bound".
+fn is_unbounded_end(end: &Oid) -> bool {
+    end.parts() == [0]
+}
+
+/// Resolves `GetNext` semantics for a single range via [`next_object`],
+/// bounded above by `range.end` when it's a real bound (not the "no
+/// restriction" null OID). `Value::EndOfMibView()` when nothing in range
+/// qualifies, same as a manager would see walking off the end of the MIB.
+fn resolve_get_next(handlers: &[Registration], range: &SearchRange) -> VarBind {
+    match next_object(handlers, &range.start, range.include) {
+        Some((oid, value)) if is_unbounded_end(&range.end) || oid.parts() < range.end.parts() => {
+            VarBind::new(oid, value)
+        }
+        _ => VarBind::new(range.start.clone(), Value::EndOfMibView()),
+    }
+}
+
+fn resolve_get_next_ranges(handlers: &[Registration], ranges: &[SearchRange]) -> Vec<VarBind> {
+    ranges
+        .iter()
+        .map(|range| resolve_get_next(handlers, range))
+        .collect()
+}
+
+/// Resolves `GetBulk` semantics: the first `non_repeaters` ranges get a
+/// single `GetNext`-style lookup each; the remaining ranges are walked up to
+/// `max_repetitions` times, each repetition resuming strictly after the OID
+/// the previous one returned, and stopping early once a range hits
+/// `EndOfMibView`.
+fn resolve_get_bulk(handlers: &[Registration], bulk: &GetBulkPdu) -> Vec<VarBind> {
+    let non_repeaters = bulk.non_repeaters as usize;
+    let mut out = Vec::new();
+
+    for range in bulk.ranges.iter().take(non_repeaters) {
+        out.push(resolve_get_next(handlers, range));
+    }
+
+    for range in bulk.ranges.iter().skip(non_repeaters) {
+        let mut cursor = range.start.clone();
+        let mut include = range.include;
+        for _ in 0..bulk.max_repetitions {
+            let vb = resolve_get_next(
+                handlers,
+                &SearchRange::new(cursor.clone(), range.end.clone(), include),
+            );
+            let hit_end = matches!(vb.value, Value::EndOfMibView());
+            cursor = vb.oid.clone();
+            include = false;
+            out.push(vb);
+            if hit_end {
+                break;
+            }
+        }
+    }
+
+    out
+}
+
+/// Drives a single AgentX session against a master agent over a blocking
+/// `Read + Write` stream (a `UnixStream` or `TcpStream` in practice).
+///
+/// Administrative PDUs (`open`/`register`/`unregister`/`ping`) send-and-wait
+/// for the matching Response before returning. Bulk-data PDUs are serviced
+/// by [`SyncClient::dispatch_once`], meant to be called in a loop by the
+/// embedder.
+pub trait SyncClient {
+    /// Sends an Open PDU and returns the `session_id` the master assigned.
+    fn open(&mut self, timeout: u8, id: Oid, description: impl Into<Vec<u8>>) -> io::Result<u32>;
+
+    /// Sends a Close PDU, ending the session.
+    fn close(&mut self, reason: CloseReason) -> io::Result<()>;
+
+    /// Registers a MIB subtree with the master and remembers `handler` for
+    /// dispatching future Get/GetNext/GetBulk/TestSet requests under it.
+    fn register(
+        &mut self,
+        subtree: Oid,
+        priority: u8,
+        timeout: u8,
+        handler: Handler,
+    ) -> io::Result<()>;
+
+    /// Unregisters a previously-registered subtree and drops its handler.
+    fn unregister(&mut self, subtree: Oid, priority: u8) -> io::Result<()>;
+
+    /// Sends a Ping keepalive and waits for the Response.
+    fn ping(&mut self) -> io::Result<()>;
+
+    /// Reads one inbound PDU, routes it to the matching registered
+    /// handler(s), and writes back a Response. Returns `Ok(false)` once the
+    /// peer has closed the connection cleanly.
+    fn dispatch_once(&mut self) -> io::Result<bool>;
+}
+
+/// The blocking [`SyncClient`] implementation: a thin state machine (session id, packet id counter, registered handlers) over
+/// any blocking duplex stream.
+pub struct BlockingSession<S> {
+    stream: S,
+    session_id: u32,
+    transaction_id: u32,
+    packet_ids: PacketIds,
+    handlers: Vec<Registration>,
+    /// `(timeout, id, description)` from the last successful [`Self::open`],
+    /// kept around so [`Self::reconnect`] can replay it against a new
+    /// stream without the embedder having to remember its own Open params.
+    open_params: Option<(u8, Oid, Vec<u8>)>,
+    /// Limits applied to every PDU this session decodes off the wire in
+    /// [`SyncClient::dispatch_once`]. Defaults to [`DecodeLimits::default`];
+    /// override with [`Self::with_decode_limits`] before dispatching against
+    /// a master agent that warrants tighter bounds.
+    decode_limits: DecodeLimits,
+}
+
+impl<S: Read + Write> BlockingSession<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            session_id: 0,
+            transaction_id: 0,
+            packet_ids: PacketIds::default(),
+            handlers: Vec::new(),
+            open_params: None,
+            decode_limits: DecodeLimits::default(),
+        }
+    }
+
+    /// Overrides the [`DecodeLimits`] applied to PDUs this session decodes
+    /// in [`SyncClient::dispatch_once`].
+    pub fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = decode_limits;
+        self
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    /// Swaps in a freshly-connected `stream` (e.g. after `dispatch_once`/
+    /// `send` returned an `Err` and the embedder redialed the master) and
+    /// replays Open followed by every currently-registered subtree's
+    /// Register, so the new connection ends up in the same state as the
+    /// one it replaces.
+    ///
+    /// Errors if this session was never successfully opened (nothing to
+    /// replay; `stream` is then dropped without being installed) or if the
+    /// replay itself fails; in the latter case `stream` has already been
+    /// installed, so `self.session_id()` and a retry reflect the new
+    /// connection rather than the dead one.
+    pub fn reconnect(&mut self, stream: S) -> io::Result<()> {
+        let (timeout, id, description) = self.open_params.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "reconnect called before the session was ever opened",
+            )
+        })?;
+
+        self.stream = stream;
+        self.transaction_id = 0;
+        self.packet_ids = PacketIds::default();
+
+        SyncClient::open(self, timeout, id, description)?;
+
+        let handlers = std::mem::take(&mut self.handlers);
+        for (subtree, priority, timeout, handler) in handlers {
+            SyncClient::register(self, subtree, priority, timeout, handler)?;
+        }
+
+        Ok(())
+    }
+
+    fn send(&mut self, pdu_type: PduType, body: &[u8]) -> io::Result<()> {
+        let header = Header::new(
+            pdu_type,
+            self.session_id,
+            self.transaction_id,
+            self.packet_ids.next(),
+        )
+        .with_payload_length(body.len() as u32);
+
+        header.encode(&mut self.stream)?;
+        self.stream.write_all(body)?;
+        Ok(())
+    }
+
+    fn send_and_confirm(&mut self, pdu_type: PduType, body: &[u8]) -> io::Result<(Header, ResponsePdu)> {
+        self.send(pdu_type, body)?;
+
+        let header = Header::decode(&mut self.stream)?;
+        if header.pdu_type != PduType::Response {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected Response, got {:?}", header.pdu_type),
+            ));
+        }
+        let response = ResponsePdu::decode(&mut self.stream, header.payload_length as usize)?;
+        Ok((header, response))
+    }
+}
+
+impl<S: Read + Write> SyncClient for BlockingSession<S> {
+    fn open(&mut self, timeout: u8, id: Oid, description: impl Into<Vec<u8>>) -> io::Result<u32> {
+        let description = description.into();
+        let open = OpenPdu::new(timeout, id.clone(), description.clone());
+        let mut body = Vec::new();
+        open.encode(&mut body)?;
+
+        let (header, response) = self.send_and_confirm(PduType::Open, &body)?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("open", response.error));
+        }
+
+        self.session_id = header.session_id;
+        self.open_params = Some((timeout, id, description));
+        Ok(self.session_id)
+    }
+
+    fn close(&mut self, reason: CloseReason) -> io::Result<()> {
+        let close = ClosePdu::new(reason);
+        let mut body = Vec::new();
+        close.encode(&mut body)?;
+        self.send(PduType::Close, &body)
+    }
+
+    fn register(
+        &mut self,
+        subtree: Oid,
+        priority: u8,
+        timeout: u8,
+        handler: Handler,
+    ) -> io::Result<()> {
+        let register = RegisterPdu::new(subtree.clone(), priority, timeout);
+        let mut body = Vec::new();
+        register.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Register, &body)?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("register", response.error));
+        }
+
+        self.handlers.push((subtree, priority, timeout, handler));
+        Ok(())
+    }
+
+    fn unregister(&mut self, subtree: Oid, priority: u8) -> io::Result<()> {
+        let unregister = UnregisterPdu::new(subtree.clone(), priority);
+        let mut body = Vec::new();
+        unregister.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Unregister, &body)?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("unregister", response.error));
+        }
+
+        self.handlers
+            .retain(|(s, p, _, _)| !(s.to_string() == subtree.to_string() && *p == priority));
+        Ok(())
+    }
+
+    fn ping(&mut self) -> io::Result<()> {
+        let ping = PingPdu::new();
+        let mut body = Vec::new();
+        ping.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Ping, &body)?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("ping", response.error));
+        }
+        Ok(())
+    }
+
+    fn dispatch_once(&mut self) -> io::Result<bool> {
+        let header = match Header::decode(&mut self.stream) {
+            Ok(header) => header,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        self.transaction_id = header.transaction_id;
+        let payload_len = header.payload_length as usize;
+
+        let varbinds = match header.pdu_type {
+            PduType::Get => {
+                let get = GetPdu::decode_limited(&mut self.stream, payload_len, &self.decode_limits)?;
+                resolve_get_ranges(&self.handlers, &get.ranges)
+            }
+            PduType::GetNext => {
+                let get = GetPdu::decode_limited(&mut self.stream, payload_len, &self.decode_limits)?;
+                resolve_get_next_ranges(&self.handlers, &get.ranges)
+            }
+            PduType::GetBulk => {
+                let bulk = GetBulkPdu::decode_bo_limited(
+                    &mut self.stream,
+                    payload_len,
+                    Endianness::Big,
+                    &self.decode_limits,
+                )?;
+                resolve_get_bulk(&self.handlers, &bulk)
+            }
+            PduType::TestSet => {
+                let test_set =
+                    TestSetPdu::decode_limited(&mut self.stream, payload_len, &self.decode_limits)?;
+                test_set.varbinds
+            }
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("dispatch_once does not handle {other:?}"),
+                ));
+            }
+        };
+
+        let response = ResponsePdu::new(0, varbinds);
+        let mut body = Vec::new();
+        response.encode(&mut body)?;
+        self.session_id = header.session_id;
+        self.send(PduType::Response, &body)?;
+
+        Ok(true)
+    }
+}
+
+/// Async counterpart to [`SyncClient`]. Administrative PDUs are still
+/// awaited (the async variant gains its value from not blocking an entire
+/// OS thread while waiting for the master, not from firing-and-forgetting
+/// the Response), and `dispatch_once` is expected to run inside the
+/// embedder's own event loop alongside other work.
+#[cfg(feature = "async")]
+pub trait AsyncClient {
+    fn open(
+        &mut self,
+        timeout: u8,
+        id: Oid,
+        description: Vec<u8>,
+    ) -> impl std::future::Future<Output = io::Result<u32>> + Send;
+
+    fn register(
+        &mut self,
+        subtree: Oid,
+        priority: u8,
+        timeout: u8,
+        handler: Handler,
+    ) -> impl std::future::Future<Output = io::Result<()>> + Send;
+
+    fn unregister(
+        &mut self,
+        subtree: Oid,
+        priority: u8,
+    ) -> impl std::future::Future<Output = io::Result<()>> + Send;
+
+    fn ping(&mut self) -> impl std::future::Future<Output = io::Result<()>> + Send;
+
+    fn dispatch_once(&mut self) -> impl std::future::Future<Output = io::Result<bool>> + Send;
+}
+
+#[cfg(feature = "async")]
+pub struct TokioSession<S> {
+    stream: S,
+    session_id: u32,
+    transaction_id: u32,
+    packet_ids: PacketIds,
+    handlers: Vec<Registration>,
+    /// `(timeout, id, description)` from the last successful [`Self::open`],
+    /// kept around so [`Self::reconnect`] can replay it against a new
+    /// stream without the embedder having to remember its own Open params.
+    open_params: Option<(u8, Oid, Vec<u8>)>,
+    /// Limits applied to every PDU this session decodes off the wire in
+    /// [`AsyncClient::dispatch_once`]. Defaults to [`DecodeLimits::default`];
+    /// override with [`Self::with_decode_limits`] before dispatching against
+    /// a master agent that warrants tighter bounds.
+    decode_limits: DecodeLimits,
+}
+
+#[cfg(feature = "async")]
+impl<S> TokioSession<S>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            session_id: 0,
+            transaction_id: 0,
+            packet_ids: PacketIds::default(),
+            handlers: Vec::new(),
+            open_params: None,
+            decode_limits: DecodeLimits::default(),
+        }
+    }
+
+    /// Overrides the [`DecodeLimits`] applied to PDUs this session decodes
+    /// in [`AsyncClient::dispatch_once`].
+    pub fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = decode_limits;
+        self
+    }
+
+    /// Async counterpart to [`BlockingSession::reconnect`]: swaps in a
+    /// freshly-connected `stream` and replays Open followed by every
+    /// registered subtree's Register against it.
+    pub async fn reconnect(&mut self, stream: S) -> io::Result<()> {
+        let (timeout, id, description) = self.open_params.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "reconnect called before the session was ever opened",
+            )
+        })?;
+
+        self.stream = stream;
+        self.transaction_id = 0;
+        self.packet_ids = PacketIds::default();
+
+        AsyncClient::open(self, timeout, id, description).await?;
+
+        let handlers = std::mem::take(&mut self.handlers);
+        for (subtree, priority, timeout, handler) in handlers {
+            AsyncClient::register(self, subtree, priority, timeout, handler).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send(&mut self, pdu_type: PduType, body: &[u8]) -> io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let header = Header::new(
+            pdu_type,
+            self.session_id,
+            self.transaction_id,
+            self.packet_ids.next(),
+        )
+        .with_payload_length(body.len() as u32);
+
+        let mut buf = Vec::new();
+        header.encode(&mut buf)?;
+        buf.extend_from_slice(body);
+        self.stream.write_all(&buf).await
+    }
+
+    async fn recv_header(&mut self) -> io::Result<Header> {
+        use tokio::io::AsyncReadExt;
+
+        let mut buf = [0u8; super::header::HEADER_SIZE];
+        self.stream.read_exact(&mut buf).await?;
+        Header::decode(&mut buf.as_slice())
+    }
+
+    async fn send_and_confirm(
+        &mut self,
+        pdu_type: PduType,
+        body: &[u8],
+    ) -> io::Result<(Header, ResponsePdu)> {
+        use tokio::io::AsyncReadExt;
+
+        self.send(pdu_type, body).await?;
+
+        let header = self.recv_header().await?;
+        if header.pdu_type != PduType::Response {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected Response, got {:?}", header.pdu_type),
+            ));
+        }
+
+        if header.payload_length > self.decode_limits.max_pdu_body_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "PDU body length ({}) exceeds limit ({})",
+                    header.payload_length, self.decode_limits.max_pdu_body_len
+                ),
+            ));
+        }
+        let mut payload = vec![0u8; header.payload_length as usize];
+        self.stream.read_exact(&mut payload).await?;
+        let response = ResponsePdu::decode(&mut payload.as_slice(), payload.len())?;
+        Ok((header, response))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncClient for TokioSession<S>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send,
+{
+    async fn open(&mut self, timeout: u8, id: Oid, description: Vec<u8>) -> io::Result<u32> {
+        let open = OpenPdu::new(timeout, id.clone(), description.clone());
+        let mut body = Vec::new();
+        open.encode(&mut body)?;
+
+        let (header, response) = self.send_and_confirm(PduType::Open, &body).await?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("open", response.error));
+        }
+
+        self.session_id = header.session_id;
+        self.open_params = Some((timeout, id, description));
+        Ok(self.session_id)
+    }
+
+    async fn register(
+        &mut self,
+        subtree: Oid,
+        priority: u8,
+        timeout: u8,
+        handler: Handler,
+    ) -> io::Result<()> {
+        let register = RegisterPdu::new(subtree.clone(), priority, timeout);
+        let mut body = Vec::new();
+        register.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Register, &body).await?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("register", response.error));
+        }
+
+        self.handlers.push((subtree, priority, timeout, handler));
+        Ok(())
+    }
+
+    async fn unregister(&mut self, subtree: Oid, priority: u8) -> io::Result<()> {
+        let unregister = UnregisterPdu::new(subtree.clone(), priority);
+        let mut body = Vec::new();
+        unregister.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Unregister, &body).await?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("unregister", response.error));
+        }
+
+        self.handlers
+            .retain(|(s, p, _, _)| !(s.to_string() == subtree.to_string() && *p == priority));
+        Ok(())
+    }
+
+    async fn ping(&mut self) -> io::Result<()> {
+        let ping = PingPdu::new();
+        let mut body = Vec::new();
+        ping.encode(&mut body)?;
+
+        let (_, response) = self.send_and_confirm(PduType::Ping, &body).await?;
+        if response.error != ResponseError::NoError {
+            return Err(registration_error("ping", response.error));
+        }
+        Ok(())
+    }
+
+    async fn dispatch_once(&mut self) -> io::Result<bool> {
+        use tokio::io::AsyncReadExt;
+
+        let header = match self.recv_header().await {
+            Ok(header) => header,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        self.transaction_id = header.transaction_id;
+
+        if header.payload_length > self.decode_limits.max_pdu_body_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "PDU body length ({}) exceeds limit ({})",
+                    header.payload_length, self.decode_limits.max_pdu_body_len
+                ),
+            ));
+        }
+        let mut payload = vec![0u8; header.payload_length as usize];
+        self.stream.read_exact(&mut payload).await?;
+        let mut payload = payload.as_slice();
+
+        let varbinds = match header.pdu_type {
+            PduType::Get => {
+                let get = GetPdu::decode_limited(
+                    &mut payload,
+                    header.payload_length as usize,
+                    &self.decode_limits,
+                )?;
+                resolve_get_ranges(&self.handlers, &get.ranges)
+            }
+            PduType::GetNext => {
+                let get = GetPdu::decode_limited(
+                    &mut payload,
+                    header.payload_length as usize,
+                    &self.decode_limits,
+                )?;
+                resolve_get_next_ranges(&self.handlers, &get.ranges)
+            }
+            PduType::GetBulk => {
+                let bulk = GetBulkPdu::decode_bo_limited(
+                    &mut payload,
+                    header.payload_length as usize,
+                    Endianness::Big,
+                    &self.decode_limits,
+                )?;
+                resolve_get_bulk(&self.handlers, &bulk)
+            }
+            PduType::TestSet => {
+                let test_set = TestSetPdu::decode_limited(
+                    &mut payload,
+                    header.payload_length as usize,
+                    &self.decode_limits,
+                )?;
+                test_set.varbinds
+            }
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("dispatch_once does not handle {other:?}"),
+                ));
+            }
+        };
+
+        let response = ResponsePdu::new(0, varbinds);
+        let mut body = Vec::new();
+        response.encode(&mut body)?;
+        self.session_id = header.session_id;
+        self.send(PduType::Response, &body).await?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_ids_monotonic() {
+        let ids = PacketIds::default();
+        assert_eq!(ids.next(), 0);
+        assert_eq!(ids.next(), 1);
+        assert_eq!(ids.next(), 2);
+    }
+
+    #[test]
+    fn test_find_handler_picks_most_specific_subtree() {
+        let broad: Oid = "1.3.6.1.4.1".parse().unwrap();
+        let narrow: Oid = "1.3.6.1.4.1.12345".parse().unwrap();
+        let instance: Oid = "1.3.6.1.4.1.12345.1.0".parse().unwrap();
+
+        let handlers: Vec<Registration> = vec![
+            (broad, 0, 0, Box::new(|_| Some(Value::Integer(1)))),
+            (narrow, 0, 0, Box::new(|_| Some(Value::Integer(2)))),
+        ];
+
+        let value = find_handler(&handlers, &instance).unwrap()(&instance);
+        assert_eq!(value, Some(Value::Integer(2)));
+    }
+
+    #[test]
+    fn test_next_object_walks_across_registered_subtrees_in_order() {
+        let first: Oid = "1.3.6.1.4.1.1".parse().unwrap();
+        let second: Oid = "1.3.6.1.4.1.2".parse().unwrap();
+        let handlers: Vec<Registration> = vec![
+            (second.clone(), 0, 0, Box::new(|_| Some(Value::Integer(2)))),
+            (first.clone(), 0, 0, Box::new(|_| Some(Value::Integer(1)))),
+        ];
+
+        let before_both: Oid = "1.3.6.1.4.1".parse().unwrap();
+        assert_eq!(
+            next_object(&handlers, &before_both, false),
+            Some((first.clone(), Value::Integer(1)))
+        );
+        assert_eq!(
+            next_object(&handlers, &first, false),
+            Some((second.clone(), Value::Integer(2)))
+        );
+        assert_eq!(next_object(&handlers, &second, false), None);
+    }
+
+    #[test]
+    fn test_next_object_skips_subtree_whose_handler_has_no_value() {
+        let empty: Oid = "1.3.6.1.4.1.1".parse().unwrap();
+        let present: Oid = "1.3.6.1.4.1.2".parse().unwrap();
+        let handlers: Vec<Registration> = vec![
+            (empty.clone(), 0, 0, Box::new(|_| None)),
+            (present.clone(), 0, 0, Box::new(|_| Some(Value::Integer(9)))),
+        ];
+
+        let before_both: Oid = "1.3.6.1.4.1".parse().unwrap();
+        assert_eq!(
+            next_object(&handlers, &before_both, false),
+            Some((present, Value::Integer(9)))
+        );
+    }
+
+    #[test]
+    fn test_resolve_get_next_reports_end_of_mib_view_past_last_subtree() {
+        let only: Oid = "1.3.6.1.4.1.1".parse().unwrap();
+        let handlers: Vec<Registration> =
+            vec![(only.clone(), 0, 0, Box::new(|_| Some(Value::Integer(1))))];
+
+        let range = SearchRange::new(only, Oid::from_slice(&[0]).unwrap(), false);
+        let vb = resolve_get_next(&handlers, &range);
+        assert_eq!(vb.value, Value::EndOfMibView());
+    }
+
+    #[test]
+    fn test_resolve_get_bulk_walks_max_repetitions_then_stops() {
+        let first: Oid = "1.3.6.1.4.1.1".parse().unwrap();
+        let second: Oid = "1.3.6.1.4.1.2".parse().unwrap();
+        let handlers: Vec<Registration> = vec![
+            (first.clone(), 0, 0, Box::new(|_| Some(Value::Integer(1)))),
+            (second.clone(), 0, 0, Box::new(|_| Some(Value::Integer(2)))),
+        ];
+
+        let before_both: Oid = "1.3.6.1.4.1".parse().unwrap();
+        let range = SearchRange::new(before_both, Oid::from_slice(&[0]).unwrap(), false);
+        let bulk = GetBulkPdu::new(0, 3, vec![range]);
+
+        let varbinds = resolve_get_bulk(&handlers, &bulk);
+        assert_eq!(varbinds.len(), 3);
+        assert_eq!(varbinds[0].value, Value::Integer(1));
+        assert_eq!(varbinds[1].value, Value::Integer(2));
+        assert_eq!(varbinds[2].value, Value::EndOfMibView());
+    }
+
+    #[test]
+    fn test_open_roundtrip_over_in_memory_stream() {
+        use std::io::Cursor;
+
+        // Simulate the master's Response to an Open PDU: session_id 7 in
+        // the header, NoError/index 0 in the body, no varbinds.
+        let mut master_reply = Vec::new();
+        let header = Header::new(PduType::Response, 7, 0, 0).with_payload_length(8);
+        header.encode(&mut master_reply).unwrap();
+        ResponsePdu::new(0, Vec::new())
+            .encode(&mut master_reply)
+            .unwrap();
+
+        struct LoopbackStream {
+            inbound: Cursor<Vec<u8>>,
+            outbound: Vec<u8>,
+        }
+        impl Read for LoopbackStream {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.inbound.read(buf)
+            }
+        }
+        impl Write for LoopbackStream {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.outbound.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let stream = LoopbackStream {
+            inbound: Cursor::new(master_reply),
+            outbound: Vec::new(),
+        };
+        let mut session = BlockingSession::new(stream);
+
+        let session_id = session
+            .open(30, "1.3.6.1.4.1.12345".parse().unwrap(), b"test agent".to_vec())
+            .unwrap();
+
+        assert_eq!(session_id, 7);
+        assert_eq!(session.session_id(), 7);
+    }
+
+    #[test]
+    fn test_reconnect_replays_open_and_register() {
+        use std::io::Cursor;
+
+        // An Open or Register Response with `session_id` in the header and
+        // NoError/no varbinds in the body — both calls replayed by
+        // `reconnect` wait on exactly this shape.
+        fn ok_response(session_id: u32) -> Vec<u8> {
+            let mut buf = Vec::new();
+            Header::new(PduType::Response, session_id, 0, 0)
+                .with_payload_length(8)
+                .encode(&mut buf)
+                .unwrap();
+            ResponsePdu::new(0, Vec::new()).encode(&mut buf).unwrap();
+            buf
+        }
+
+        struct LoopbackStream {
+            inbound: Cursor<Vec<u8>>,
+            outbound: Vec<u8>,
+        }
+        impl Read for LoopbackStream {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.inbound.read(buf)
+            }
+        }
+        impl Write for LoopbackStream {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.outbound.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut first_inbound = ok_response(7); // Open
+        first_inbound.extend(ok_response(7)); // Register
+        let first = LoopbackStream {
+            inbound: Cursor::new(first_inbound),
+            outbound: Vec::new(),
+        };
+        let mut session = BlockingSession::new(first);
+
+        session
+            .open(30, "1.3.6.1.4.1.12345".parse().unwrap(), b"test agent".to_vec())
+            .unwrap();
+        session
+            .register(
+                "1.3.6.1.4.1.12345".parse().unwrap(),
+                0,
+                30,
+                Box::new(|_| Some(Value::Integer(1))),
+            )
+            .unwrap();
+
+        // Simulate the master dropping the connection, then a fresh one
+        // being dialed by the embedder with a different session_id.
+        let mut second_inbound = ok_response(9); // Open replay
+        second_inbound.extend(ok_response(9)); // Register replay
+        let second = LoopbackStream {
+            inbound: Cursor::new(second_inbound),
+            outbound: Vec::new(),
+        };
+        session.reconnect(second).unwrap();
+
+        assert_eq!(session.session_id(), 9);
+        assert_eq!(session.handlers.len(), 1);
+    }
+}
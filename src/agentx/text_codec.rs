@@ -0,0 +1,385 @@
+//! A human-readable textual codec, implementing the same
+//! [`Encoder`]/[`Decoder`] pair as [`super::codec::BinaryCodec`], so PDUs
+//! can be dumped to a readable form and parsed back for debugging and
+//! golden-file tests without going through the raw network encoding.
+//!
+//! Each type is written as a single line; a [`Value`] looks like
+//! `Integer(42)` or `OctetString(68656c6c6f)` (octet strings and opaques
+//! are hex-encoded to stay one line and byte-exact).
+
+use std::io::{self, Read, Write};
+
+use crate::oid::Oid;
+use crate::types::Value;
+
+use super::codec::{Decoder, Encoder};
+use super::header::{Flags, Header, PduType};
+use super::pdu::{SearchRange, VarBind};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextCodec;
+
+fn invalid(what: &str, text: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid textual {what}: {text:?}"),
+    )
+}
+
+fn write_line<W: Write>(writer: &mut W, line: &str) -> io::Result<()> {
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\n")
+}
+
+fn read_line<R: Read>(reader: &mut R) -> io::Result<String> {
+    // Byte-at-a-time rather than `read_to_string`: a dump is several lines
+    // decoded off one shared reader (header, then each varbind), so this
+    // must stop at the next `\n` and leave the rest of the stream alone.
+    let mut bytes = Vec::new();
+    let mut byte = [0u8; 1];
+    while reader.read(&mut byte)? != 0 {
+        if byte[0] == b'\n' {
+            break;
+        }
+        bytes.push(byte[0]);
+    }
+
+    let text =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(text.trim().to_string())
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(text: &str) -> io::Result<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return Err(invalid("hex string", text));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| invalid("hex string", text)))
+        .collect()
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Integer(v) => format!("Integer({v})"),
+        Value::OctetString(v) => format!("OctetString({})", encode_hex(v)),
+        Value::Null() => "Null".to_string(),
+        Value::ObjectIdentifier(oid) => format!("ObjectIdentifier({oid})"),
+        Value::IpAddress(a, b, c, d) => format!("IpAddress({a}.{b}.{c}.{d})"),
+        Value::Counter32(v) => format!("Counter32({v})"),
+        Value::Gauge32(v) => format!("Gauge32({v})"),
+        Value::TimeTicks(v) => format!("TimeTicks({v})"),
+        Value::Opaque(v) => format!("Opaque({})", encode_hex(v)),
+        Value::Counter64(v) => format!("Counter64({v})"),
+        Value::NoSuchObject() => "NoSuchObject".to_string(),
+        Value::NoSuchInstance() => "NoSuchInstance".to_string(),
+        Value::EndOfMibView() => "EndOfMibView".to_string(),
+    }
+}
+
+fn parse_value(text: &str) -> io::Result<Value> {
+    match text {
+        "Null" => return Ok(Value::Null()),
+        "NoSuchObject" => return Ok(Value::NoSuchObject()),
+        "NoSuchInstance" => return Ok(Value::NoSuchInstance()),
+        "EndOfMibView" => return Ok(Value::EndOfMibView()),
+        _ => {}
+    }
+
+    let (tag, rest) = text.split_once('(').ok_or_else(|| invalid("value", text))?;
+    let inner = rest.strip_suffix(')').ok_or_else(|| invalid("value", text))?;
+
+    match tag {
+        "Integer" => inner
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| invalid("value", text)),
+        "OctetString" => decode_hex(inner).map(Value::OctetString),
+        "ObjectIdentifier" => inner
+            .parse::<Oid>()
+            .map(Value::ObjectIdentifier)
+            .map_err(|_| invalid("value", text)),
+        "IpAddress" => {
+            let octets: Vec<&str> = inner.split('.').collect();
+            if octets.len() != 4 {
+                return Err(invalid("value", text));
+            }
+            let mut parsed = [0u8; 4];
+            for (slot, octet) in parsed.iter_mut().zip(&octets) {
+                *slot = octet.parse().map_err(|_| invalid("value", text))?;
+            }
+            Ok(Value::IpAddress(parsed[0], parsed[1], parsed[2], parsed[3]))
+        }
+        "Counter32" => inner
+            .parse()
+            .map(Value::Counter32)
+            .map_err(|_| invalid("value", text)),
+        "Gauge32" => inner
+            .parse()
+            .map(Value::Gauge32)
+            .map_err(|_| invalid("value", text)),
+        "TimeTicks" => inner
+            .parse()
+            .map(Value::TimeTicks)
+            .map_err(|_| invalid("value", text)),
+        "Opaque" => decode_hex(inner).map(Value::Opaque),
+        "Counter64" => inner
+            .parse()
+            .map(Value::Counter64)
+            .map_err(|_| invalid("value", text)),
+        _ => Err(invalid("value", text)),
+    }
+}
+
+fn format_varbind(varbind: &VarBind) -> String {
+    format!("{} = {}", varbind.oid, format_value(&varbind.value))
+}
+
+fn parse_varbind(text: &str) -> io::Result<VarBind> {
+    let (oid, value) = text
+        .split_once(" = ")
+        .ok_or_else(|| invalid("varbind", text))?;
+    let oid: Oid = oid.parse().map_err(|_| invalid("varbind", text))?;
+    Ok(VarBind::new(oid, parse_value(value)?))
+}
+
+fn format_search_range(range: &SearchRange) -> String {
+    format!(
+        "{}{}..{}",
+        if range.include { "!" } else { "" },
+        range.start,
+        range.end
+    )
+}
+
+fn parse_search_range(text: &str) -> io::Result<SearchRange> {
+    let (include, rest) = match text.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (start, end) = rest
+        .split_once("..")
+        .ok_or_else(|| invalid("search range", text))?;
+    let start: Oid = start.parse().map_err(|_| invalid("search range", text))?;
+    let end: Oid = end.parse().map_err(|_| invalid("search range", text))?;
+    Ok(SearchRange::new(start, end, include))
+}
+
+fn pdu_type_name(pdu_type: PduType) -> &'static str {
+    match pdu_type {
+        PduType::Open => "Open",
+        PduType::Close => "Close",
+        PduType::Register => "Register",
+        PduType::Unregister => "Unregister",
+        PduType::Get => "Get",
+        PduType::GetNext => "GetNext",
+        PduType::GetBulk => "GetBulk",
+        PduType::TestSet => "TestSet",
+        PduType::CommitSet => "CommitSet",
+        PduType::UndoSet => "UndoSet",
+        PduType::CleanupSet => "CleanupSet",
+        PduType::Notify => "Notify",
+        PduType::Ping => "Ping",
+        PduType::IndexAllocate => "IndexAllocate",
+        PduType::IndexDeallocate => "IndexDeallocate",
+        PduType::AddAgentCaps => "AddAgentCaps",
+        PduType::RemoveAgentCaps => "RemoveAgentCaps",
+        PduType::Response => "Response",
+    }
+}
+
+fn parse_pdu_type(name: &str) -> Option<PduType> {
+    Some(match name {
+        "Open" => PduType::Open,
+        "Close" => PduType::Close,
+        "Register" => PduType::Register,
+        "Unregister" => PduType::Unregister,
+        "Get" => PduType::Get,
+        "GetNext" => PduType::GetNext,
+        "GetBulk" => PduType::GetBulk,
+        "TestSet" => PduType::TestSet,
+        "CommitSet" => PduType::CommitSet,
+        "UndoSet" => PduType::UndoSet,
+        "CleanupSet" => PduType::CleanupSet,
+        "Notify" => PduType::Notify,
+        "Ping" => PduType::Ping,
+        "IndexAllocate" => PduType::IndexAllocate,
+        "IndexDeallocate" => PduType::IndexDeallocate,
+        "AddAgentCaps" => PduType::AddAgentCaps,
+        "RemoveAgentCaps" => PduType::RemoveAgentCaps,
+        "Response" => PduType::Response,
+        _ => return None,
+    })
+}
+
+fn format_header(header: &Header) -> String {
+    format!(
+        "Header(version={}, pdu_type={}, flags={:#04x}, session_id={}, transaction_id={}, packet_id={}, payload_length={})",
+        header.version,
+        pdu_type_name(header.pdu_type),
+        header.flags.bits(),
+        header.session_id,
+        header.transaction_id,
+        header.packet_id,
+        header.payload_length,
+    )
+}
+
+fn parse_header(text: &str) -> io::Result<Header> {
+    let inner = text
+        .strip_prefix("Header(")
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| invalid("header", text))?;
+
+    let mut version = None;
+    let mut pdu_type = None;
+    let mut flags = None;
+    let mut session_id = None;
+    let mut transaction_id = None;
+    let mut packet_id = None;
+    let mut payload_length = None;
+
+    for field in inner.split(", ") {
+        let (key, value) = field.split_once('=').ok_or_else(|| invalid("header", text))?;
+        match key {
+            "version" => version = value.parse().ok(),
+            "pdu_type" => pdu_type = parse_pdu_type(value),
+            "flags" => {
+                flags = value
+                    .strip_prefix("0x")
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .map(Flags::from_bits_truncate)
+            }
+            "session_id" => session_id = value.parse().ok(),
+            "transaction_id" => transaction_id = value.parse().ok(),
+            "packet_id" => packet_id = value.parse().ok(),
+            "payload_length" => payload_length = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Ok(Header {
+        version: version.ok_or_else(|| invalid("header", text))?,
+        pdu_type: pdu_type.ok_or_else(|| invalid("header", text))?,
+        flags: flags.ok_or_else(|| invalid("header", text))?,
+        session_id: session_id.ok_or_else(|| invalid("header", text))?,
+        transaction_id: transaction_id.ok_or_else(|| invalid("header", text))?,
+        packet_id: packet_id.ok_or_else(|| invalid("header", text))?,
+        payload_length: payload_length.ok_or_else(|| invalid("header", text))?,
+    })
+}
+
+impl Encoder for TextCodec {
+    fn encode_header<W: Write>(&self, writer: &mut W, header: &Header) -> io::Result<()> {
+        write_line(writer, &format_header(header))
+    }
+
+    fn encode_varbind<W: Write>(&self, writer: &mut W, varbind: &VarBind) -> io::Result<()> {
+        write_line(writer, &format_varbind(varbind))
+    }
+
+    fn encode_search_range<W: Write>(
+        &self,
+        writer: &mut W,
+        range: &SearchRange,
+    ) -> io::Result<()> {
+        write_line(writer, &format_search_range(range))
+    }
+
+    fn encode_value<W: Write>(&self, writer: &mut W, value: &Value) -> io::Result<()> {
+        write_line(writer, &format_value(value))
+    }
+}
+
+impl Decoder for TextCodec {
+    fn decode_header<R: Read>(&self, reader: &mut R) -> io::Result<Header> {
+        parse_header(&read_line(reader)?)
+    }
+
+    fn decode_varbind<R: Read>(&self, reader: &mut R) -> io::Result<VarBind> {
+        parse_varbind(&read_line(reader)?)
+    }
+
+    fn decode_search_range<R: Read>(&self, reader: &mut R) -> io::Result<SearchRange> {
+        parse_search_range(&read_line(reader)?)
+    }
+
+    fn decode_value<R: Read>(&self, reader: &mut R) -> io::Result<Value> {
+        parse_value(&read_line(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_codec_value_roundtrip() {
+        let codec = TextCodec;
+        for value in [
+            Value::Integer(-42),
+            Value::OctetString(b"hello".to_vec()),
+            Value::Null(),
+            Value::Counter64(u64::MAX),
+            Value::IpAddress(192, 0, 2, 1),
+            Value::NoSuchInstance(),
+        ] {
+            let mut buf = Vec::new();
+            codec.encode_value(&mut buf, &value).unwrap();
+
+            let decoded = codec.decode_value(&mut buf.as_slice()).unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_text_codec_varbind_roundtrip() {
+        let codec = TextCodec;
+        let oid: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        let varbind = VarBind::new(oid, Value::Integer(7));
+
+        let mut buf = Vec::new();
+        codec.encode_varbind(&mut buf, &varbind).unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "1.3.6.1.2.1.1.1.0 = Integer(7)\n"
+        );
+
+        let decoded = codec.decode_varbind(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, varbind);
+    }
+
+    #[test]
+    fn test_text_codec_header_roundtrip() {
+        let codec = TextCodec;
+        let header = Header::new(PduType::GetBulk, 1, 2, 3).with_payload_length(16);
+
+        let mut buf = Vec::new();
+        codec.encode_header(&mut buf, &header).unwrap();
+
+        let decoded = codec.decode_header(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, header);
+    }
+
+    #[test]
+    fn test_text_codec_decodes_multiple_lines_off_one_reader() {
+        let codec = TextCodec;
+        let header = Header::new(PduType::Response, 1, 2, 3).with_payload_length(16);
+        let varbind_a = VarBind::new("1.3.6.1.2.1.1.1.0".parse().unwrap(), Value::Integer(1));
+        let varbind_b = VarBind::new("1.3.6.1.2.1.1.2.0".parse().unwrap(), Value::Integer(2));
+
+        let mut buf = Vec::new();
+        codec.encode_header(&mut buf, &header).unwrap();
+        codec.encode_varbind(&mut buf, &varbind_a).unwrap();
+        codec.encode_varbind(&mut buf, &varbind_b).unwrap();
+
+        let mut reader = buf.as_slice();
+        assert_eq!(codec.decode_header(&mut reader).unwrap(), header);
+        assert_eq!(codec.decode_varbind(&mut reader).unwrap(), varbind_a);
+        assert_eq!(codec.decode_varbind(&mut reader).unwrap(), varbind_b);
+    }
+}
@@ -1,9 +1,18 @@
+// The PDU codec (`agentx::{pdu, bodies, header, codec, io}`) builds with
+// `--no-default-features` against `core_io` for embedded/RTOS targets; the
+// Python bindings below need a real allocator and OS, so they (and the
+// session, text codec and vectored-write code) stay behind the default
+// `std` feature.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
 use pyo3::prelude::*;
 
 pub mod agentx;
 pub mod oid;
 pub mod types;
 
+#[cfg(feature = "std")]
 #[pymodule(name = "core")]
 fn snmpkit_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;